@@ -1,11 +1,21 @@
 extern crate rand;
+extern crate serde;
+extern crate serde_json;
 extern crate web_sys;
 
 mod utils;
 
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use web_sys::CanvasRenderingContext2d;
 
@@ -23,10 +33,54 @@ macro_rules! log {
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 const EMPTY_COLOR: &str = "rgb(255, 255, 255)";
-const FILL_COLOR: &str = "rgb(0, 0, 0)";
 const ANT_COLOR: &str = "rgb(200, 0, 0)";
 
-#[derive(Clone, Debug)]
+// Distinct, cycling colors used to tell a colony's ants apart on screen.
+const ANT_COLORS: [&str; 6] = [
+    "rgb(200, 0, 0)",
+    "rgb(0, 120, 200)",
+    "rgb(0, 160, 60)",
+    "rgb(180, 120, 0)",
+    "rgb(140, 0, 160)",
+    "rgb(0, 160, 160)",
+];
+
+// Builds a grayscale palette running from `EMPTY_COLOR` (color 0) to black
+// (the last color), so the classic 2-color behavior is preserved by default
+// while still generalizing to `colors` shades.
+fn default_palette(colors: usize) -> Vec<String> {
+    if colors <= 1 {
+        return vec![EMPTY_COLOR.to_string()];
+    }
+
+    (0..colors)
+        .map(|i| {
+            let shade = 255 - (255 * i / (colors - 1));
+            format!("rgb({0}, {0}, {0})", shade)
+        })
+        .collect()
+}
+
+// How many recent steps/state signatures `Turmite` keeps for highway/cycle detection.
+const HISTORY_CAPACITY: usize = 4096;
+const NEIGHBORHOOD_RADIUS: i32 = 2;
+const HIGHWAY_MIN_REPEATS: usize = 3;
+// Real highways settle into small periods; capping the search here keeps
+// `detect_highway` cheap enough to run every tick even once the history
+// ring buffers are full.
+const HIGHWAY_MAX_PERIOD: usize = 64;
+
+// Builds a seeded RNG when `seed` is given so a (seed, rule) pair always
+// reproduces the same pattern across machines; falls back to OS entropy
+// otherwise, matching the previous implicit `rand::thread_rng()` behavior.
+fn make_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum Rotate {
     Clockwise,
     CounterClockwise,
@@ -34,15 +88,15 @@ enum Rotate {
     Uturn,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct Decision {
     rotate: Rotate,
-    color: bool,
-    state: bool,
+    color: usize,
+    state: usize,
 }
 
 impl Decision {
-    fn new(rotate: Rotate, color: bool, state: bool) -> Decision {
+    fn new(rotate: Rotate, color: usize, state: usize) -> Decision {
         Decision {
             rotate,
             color,
@@ -51,154 +105,317 @@ impl Decision {
     }
 }
 
+fn char_to_rotate(c: char) -> Result<Rotate, String> {
+    use Rotate::*;
+
+    match c {
+        'L' => Ok(CounterClockwise),
+        'R' => Ok(Clockwise),
+        'N' => Ok(Noop),
+        'U' => Ok(Uturn),
+        other => Err(format!(
+            "Unknown turn character '{}', expected one of L, R, N, U",
+            other
+        )),
+    }
+}
+
+fn rotate_to_char(rotate: &Rotate) -> char {
+    use Rotate::*;
+
+    match rotate {
+        CounterClockwise => 'L',
+        Clockwise => 'R',
+        Noop => 'N',
+        Uturn => 'U',
+    }
+}
+
 macro_rules! decision_table {
-    ($name:expr, $( [$rotate:ident, $color:ident, $state:ident] ),*) => {
-        DecisionTable {
-            name: $name,
-            table: [
-                $( Decision::new($rotate, $color, $state) ),*
-            ],
+    ($name:expr, $colors:expr, $( [$rotate:ident, $color:expr, $state:expr] ),*) => {
+        {
+            let table = vec![ $( Decision::new($rotate, $color, $state) ),* ];
+            let colors = $colors;
+            let states = table.len() / colors;
+            DecisionTable { name: $name.to_string(), colors, states, table }
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct DecisionTable {
-    name: &'static str,
-    table: [Decision; 4],
+    name: String,
+    colors: usize,
+    states: usize,
+    table: Vec<Decision>,
 }
 
 impl DecisionTable {
-    fn random() -> DecisionTable {
+    fn random(rng: &mut StdRng) -> DecisionTable {
         use Rotate::*;
 
         let tables = vec![
             decision_table!(
                 "fibonacci",
-                [CounterClockwise, true, true],
-                [CounterClockwise, true, true],
-                [Clockwise, true, true],
-                [Noop, false, false]
+                2,
+                [CounterClockwise, 1, 1],
+                [CounterClockwise, 1, 1],
+                [Clockwise, 1, 1],
+                [Noop, 0, 0]
             ),
             decision_table!(
                 "langton",
-                [Clockwise, true, false],
-                [CounterClockwise, false, false],
-                [Clockwise, true, false],
-                [CounterClockwise, false, false]
+                2,
+                [Clockwise, 1, 0],
+                [CounterClockwise, 0, 0],
+                [Clockwise, 1, 0],
+                [CounterClockwise, 0, 0]
             ),
             decision_table!(
                 "chaotic_one",
-                [Clockwise, true, false],
-                [Clockwise, true, true],
-                [Noop, false, false],
-                [Noop, false, true]
+                2,
+                [Clockwise, 1, 0],
+                [Clockwise, 1, 1],
+                [Noop, 0, 0],
+                [Noop, 0, 1]
             ),
             decision_table!(
                 "chaotic_two",
-                [Clockwise, true, true],
-                [CounterClockwise, false, true],
-                [Noop, true, false],
-                [Noop, false, true]
+                2,
+                [Clockwise, 1, 1],
+                [CounterClockwise, 0, 1],
+                [Noop, 1, 0],
+                [Noop, 0, 1]
             ),
             decision_table!(
                 "chaotic_three",
-                [CounterClockwise, true, true],
-                [CounterClockwise, false, true],
-                [Clockwise, true, true],
-                [CounterClockwise, false, false]
+                2,
+                [CounterClockwise, 1, 1],
+                [CounterClockwise, 0, 1],
+                [Clockwise, 1, 1],
+                [CounterClockwise, 0, 0]
             ),
             decision_table!(
                 "chaotic_four",
-                [CounterClockwise, true, true],
-                [CounterClockwise, false, true],
-                [Noop, true, false],
-                [Noop, true, true]
+                2,
+                [CounterClockwise, 1, 1],
+                [CounterClockwise, 0, 1],
+                [Noop, 1, 0],
+                [Noop, 1, 1]
             ),
             decision_table!(
                 "coral",
-                [Clockwise, true, true],
-                [CounterClockwise, true, true],
-                [Clockwise, true, true],
-                [CounterClockwise, false, false]
+                2,
+                [Clockwise, 1, 1],
+                [CounterClockwise, 1, 1],
+                [Clockwise, 1, 1],
+                [CounterClockwise, 0, 0]
             ),
             decision_table!(
                 "square_one",
-                [CounterClockwise, true, false],
-                [Clockwise, true, true],
-                [Clockwise, false, false],
-                [CounterClockwise, false, true]
+                2,
+                [CounterClockwise, 1, 0],
+                [Clockwise, 1, 1],
+                [Clockwise, 0, 0],
+                [CounterClockwise, 0, 1]
             ),
             decision_table!(
                 "square_two",
-                [Clockwise, false, true],
-                [CounterClockwise, false, false],
-                [Noop, true, false],
-                [Uturn, true, true]
+                2,
+                [Clockwise, 0, 1],
+                [CounterClockwise, 0, 0],
+                [Noop, 1, 0],
+                [Uturn, 1, 1]
             ),
             decision_table!(
                 "counter_one",
-                [Noop, false, true],
-                [Uturn, false, true],
-                [Clockwise, true, true],
-                [Noop, false, true]
+                2,
+                [Noop, 0, 1],
+                [Uturn, 0, 1],
+                [Clockwise, 1, 1],
+                [Noop, 0, 1]
             ),
             decision_table!(
                 "counter_two",
-                [Clockwise, true, true],
-                [Noop, false, true],
-                [Noop, false, false],
-                [CounterClockwise, true, true]
+                2,
+                [Clockwise, 1, 1],
+                [Noop, 0, 1],
+                [Noop, 0, 0],
+                [CounterClockwise, 1, 1]
             ),
             decision_table!(
                 "spiral_one",
-                [Noop, true, true],
-                [CounterClockwise, true, false],
-                [Clockwise, true, true],
-                [Noop, false, false]
+                2,
+                [Noop, 1, 1],
+                [CounterClockwise, 1, 0],
+                [Clockwise, 1, 1],
+                [Noop, 0, 0]
             ),
             decision_table!(
                 "spiral_two",
-                [CounterClockwise, true, false],
-                [Clockwise, false, true],
-                [Clockwise, true, false],
-                [CounterClockwise, false, true]
+                2,
+                [CounterClockwise, 1, 0],
+                [Clockwise, 0, 1],
+                [Clockwise, 1, 0],
+                [CounterClockwise, 0, 1]
             ),
             decision_table!(
                 "spiral_three",
-                [Uturn, true, false],
-                [Noop, false, true],
-                [CounterClockwise, false, false],
-                [Clockwise, false, true]
+                2,
+                [Uturn, 1, 0],
+                [Noop, 0, 1],
+                [CounterClockwise, 0, 0],
+                [Clockwise, 0, 1]
             ),
             decision_table!(
                 "ladder",
-                [Noop, false, true],
-                [Uturn, true, true],
-                [CounterClockwise, true, false],
-                [Noop, true, true]
+                2,
+                [Noop, 0, 1],
+                [Uturn, 1, 1],
+                [CounterClockwise, 1, 0],
+                [Noop, 1, 1]
             ),
             decision_table!(
                 "dixie",
-                [Clockwise, false, true],
-                [CounterClockwise, false, false],
-                [Uturn, true, true],
-                [Clockwise, false, false]
+                2,
+                [Clockwise, 0, 1],
+                [CounterClockwise, 0, 0],
+                [Uturn, 1, 1],
+                [Clockwise, 0, 0]
             ),
         ];
 
         tables
-            .choose(&mut rand::thread_rng())
+            .choose(rng)
             .expect("Could not get random move table")
             .clone()
     }
 
-    fn decide(&self, x: usize, y: usize) -> Decision {
-        self.table[x * 2 + y].clone()
+    fn decide(&self, state: usize, color: usize) -> Decision {
+        self.table[state * self.colors + color].clone()
+    }
+
+    // Parses either grammar supported for sharing rules as text:
+    // - the classic generalized-Langton-ant string over `{L,R,N,U}`, one
+    //   character per color, e.g. "RL" or "LLRR"; or
+    // - a full transition table such as "{{1,R,0},{0,L,0}}" listing
+    //   `(write_color, turn, next_state)` triples row-major over
+    //   `state x color`, using `colors` to split the flat list into rows.
+    fn from_rule(spec: &str, colors: usize) -> Result<DecisionTable, String> {
+        let spec = spec.trim();
+
+        if spec.starts_with('{') {
+            DecisionTable::from_table_rule(spec, colors)
+        } else {
+            DecisionTable::from_langton_rule(spec)
+        }
+    }
+
+    fn from_langton_rule(spec: &str) -> Result<DecisionTable, String> {
+        if spec.is_empty() {
+            return Err("Rule string must not be empty".to_string());
+        }
+
+        let colors = spec.chars().count();
+        let mut table = Vec::with_capacity(colors);
+
+        for (color, c) in spec.chars().enumerate() {
+            let rotate = char_to_rotate(c)?;
+            let write_color = (color + 1) % colors;
+            table.push(Decision::new(rotate, write_color, 0));
+        }
+
+        Ok(DecisionTable {
+            name: "custom".to_string(),
+            colors,
+            states: 1,
+            table,
+        })
+    }
+
+    fn from_table_rule(spec: &str, colors: usize) -> Result<DecisionTable, String> {
+        if colors == 0 {
+            return Err("colors must be greater than zero".to_string());
+        }
+
+        let inner = spec
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(|| format!("Malformed rule table '{}', expected {{{{...}}}}", spec))?;
+
+        let mut table = Vec::new();
+
+        for entry in inner
+            .split("},{")
+            .map(|s| s.trim_matches(|c| c == '{' || c == '}'))
+        {
+            let parts: Vec<&str> = entry.split(',').map(|p| p.trim()).collect();
+            if parts.len() != 3 {
+                return Err(format!(
+                    "Malformed table entry '{}', expected color,turn,state",
+                    entry
+                ));
+            }
+
+            let write_color: usize = parts[0]
+                .parse()
+                .map_err(|_| format!("Invalid color '{}' in entry '{}'", parts[0], entry))?;
+            let turn = parts[1]
+                .chars()
+                .next()
+                .ok_or_else(|| format!("Missing turn in entry '{}'", entry))?;
+            let rotate = char_to_rotate(turn)?;
+            let next_state: usize = parts[2]
+                .parse()
+                .map_err(|_| format!("Invalid state '{}' in entry '{}'", parts[2], entry))?;
+
+            if write_color >= colors {
+                return Err(format!(
+                    "Invalid color {} in entry '{}', must be less than {} colors",
+                    write_color, entry, colors
+                ));
+            }
+
+            table.push(Decision::new(rotate, write_color, next_state));
+        }
+
+        if table.is_empty() || table.len() % colors != 0 {
+            return Err(format!(
+                "Table has {} entries, not divisible by {} colors",
+                table.len(),
+                colors
+            ));
+        }
+
+        let states = table.len() / colors;
+
+        if let Some(bad) = table.iter().find(|d| d.state >= states) {
+            return Err(format!(
+                "Invalid state {} in table, must be less than {} states",
+                bad.state, states
+            ));
+        }
+
+        Ok(DecisionTable {
+            name: "custom".to_string(),
+            colors,
+            states,
+            table,
+        })
+    }
+
+    fn to_rule(&self) -> String {
+        let entries: Vec<String> = self
+            .table
+            .iter()
+            .map(|d| format!("{{{},{},{}}}", d.color, rotate_to_char(&d.rotate), d.state))
+            .collect();
+
+        format!("{{{}}}", entries.join(","))
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 enum Orientation {
     Up,
     Right,
@@ -241,89 +458,379 @@ impl Orientation {
     }
 }
 
+fn orientation_index(orientation: &Orientation) -> u8 {
+    use Orientation::*;
+
+    match orientation {
+        Up => 0,
+        Right => 1,
+        Down => 2,
+        Left => 3,
+    }
+}
+
+// Boundary behavior at the edge of the field. `Kill` matches the original
+// behavior where the ant freezes once it steps off the grid; `Wrap` and
+// `Reflect` let long-running rules evolve indefinitely on a closed surface.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Boundary {
+    Kill,
+    Wrap,
+    Reflect,
+}
+
+// The cell grid, factored out of `Turmite` so a `Colony` can lend the same
+// grid to many ants that each read and write it independently.
+struct Field {
+    cells: Vec<Vec<u8>>,
+}
+
+impl Field {
+    fn new(width: usize, height: usize) -> Field {
+        Field {
+            cells: vec![vec![0; height]; width],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> u8 {
+        self.cells[x][y]
+    }
+
+    fn set(&mut self, x: usize, y: usize, v: u8) {
+        self.cells[x][y] = v;
+    }
+}
+
+// Bundles the parameters shared by every ant on the same field, so
+// `Turmite::new_on_field` takes one argument instead of one per field.
+struct GridSpec {
+    width: usize,
+    height: usize,
+    pixel_ratio: usize,
+    boundary: Boundary,
+    field: Rc<RefCell<Field>>,
+}
+
+// Everything needed to resume a simulation later: field contents, ant
+// position/orientation/state, and the active rule table. `palette` and
+// `ant_color` are left out since they're derived from `behavior`/defaults.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    width: usize,
+    height: usize,
+    pixel_ratio: usize,
+    x: usize,
+    y: usize,
+    orientation: Orientation,
+    state: usize,
+    is_active: bool,
+    boundary: Boundary,
+    behavior: DecisionTable,
+    cells: Vec<Vec<u8>>,
+}
+
 #[wasm_bindgen]
 pub struct Turmite {
     x: usize,
     y: usize,
     orientation: Orientation,
     behavior: DecisionTable,
-    state: bool,
+    state: usize,
     width: usize,
     height: usize,
-    field: Vec<Vec<bool>>,
+    field: Rc<RefCell<Field>>,
     pixel_ratio: usize,
     is_active: bool,
+    palette: Vec<String>,
+    boundary: Boundary,
+    ant_color: &'static str,
+    steps: u64,
+    move_history: VecDeque<(i32, i32)>,
+    state_history: VecDeque<u64>,
+    pattern: Option<String>,
 }
 
 #[wasm_bindgen]
 impl Turmite {
-    pub fn new(canvas_width: usize, canvas_height: usize, pixel_ratio: usize) -> Turmite {
+    // `seed` makes the chosen rule and the initial state/color reproducible:
+    // the same (seed, rule) pair always evolves into the identical pattern.
+    // Pass `None` to fall back to OS entropy, as before.
+    pub fn new(
+        canvas_width: usize,
+        canvas_height: usize,
+        pixel_ratio: usize,
+        boundary: Boundary,
+        seed: Option<u64>,
+    ) -> Turmite {
+        let mut rng = make_rng(seed);
+        let behavior = DecisionTable::random(&mut rng);
+        log!("Using {} as behavior table", behavior.name);
+
+        Turmite::with_behavior(
+            behavior,
+            canvas_width,
+            canvas_height,
+            pixel_ratio,
+            boundary,
+            &mut rng,
+        )
+    }
+
+    // Builds a Turmite from a rule shared as text, in either the classic
+    // Langton-ant notation or the full transition-table notation (see
+    // `DecisionTable::from_rule`). `colors` is only consulted for the
+    // table notation, where it's needed to split the flat list into rows.
+    pub fn from_rule(
+        spec: &str,
+        colors: usize,
+        canvas_width: usize,
+        canvas_height: usize,
+        pixel_ratio: usize,
+        boundary: Boundary,
+        seed: Option<u64>,
+    ) -> Result<Turmite, JsValue> {
+        let behavior =
+            DecisionTable::from_rule(spec, colors).map_err(|e| JsValue::from_str(&e))?;
+        log!("Using custom rule \"{}\" as behavior table", spec);
+
+        let mut rng = make_rng(seed);
+
+        Ok(Turmite::with_behavior(
+            behavior,
+            canvas_width,
+            canvas_height,
+            pixel_ratio,
+            boundary,
+            &mut rng,
+        ))
+    }
+
+    pub fn to_rule(&self) -> String {
+        self.behavior.to_rule()
+    }
+
+    // The color count backing `to_rule()`'s table notation, so a caller can
+    // pass it back into `from_rule`/`Colony::add_ant` to reload the rule.
+    pub fn colors(&self) -> usize {
+        self.behavior.colors
+    }
+
+    // Serializes the whole simulation (field, ant state, and rule table) so
+    // it can be restored later with `load`.
+    pub fn save(&self) -> String {
+        let snapshot = Snapshot {
+            width: self.width,
+            height: self.height,
+            pixel_ratio: self.pixel_ratio,
+            x: self.x,
+            y: self.y,
+            orientation: self.orientation.clone(),
+            state: self.state,
+            is_active: self.is_active,
+            boundary: self.boundary,
+            behavior: self.behavior.clone(),
+            cells: self.field.borrow().cells.clone(),
+        };
+
+        serde_json::to_string(&snapshot).unwrap_or_default()
+    }
+
+    pub fn load(json: &str) -> Result<Turmite, JsValue> {
+        let snapshot: Snapshot =
+            serde_json::from_str(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        if snapshot.x >= snapshot.width || snapshot.y >= snapshot.height {
+            return Err(JsValue::from_str(&format!(
+                "Snapshot position ({}, {}) is out of bounds for a {}x{} field",
+                snapshot.x, snapshot.y, snapshot.width, snapshot.height
+            )));
+        }
+
+        if snapshot.state >= snapshot.behavior.states {
+            return Err(JsValue::from_str(&format!(
+                "Snapshot state {} is out of bounds for {} states",
+                snapshot.state, snapshot.behavior.states
+            )));
+        }
+
+        if snapshot.cells.len() != snapshot.width
+            || snapshot.cells.iter().any(|col| col.len() != snapshot.height)
+        {
+            return Err(JsValue::from_str(&format!(
+                "Snapshot cells do not match the declared {}x{} field",
+                snapshot.width, snapshot.height
+            )));
+        }
+
+        let palette = default_palette(snapshot.behavior.colors);
+        let field = Rc::new(RefCell::new(Field {
+            cells: snapshot.cells,
+        }));
+
+        Ok(Turmite {
+            x: snapshot.x,
+            y: snapshot.y,
+            orientation: snapshot.orientation,
+            behavior: snapshot.behavior,
+            state: snapshot.state,
+            width: snapshot.width,
+            height: snapshot.height,
+            field,
+            pixel_ratio: snapshot.pixel_ratio,
+            is_active: snapshot.is_active,
+            palette,
+            boundary: snapshot.boundary,
+            ant_color: ANT_COLOR,
+            steps: 0,
+            move_history: VecDeque::with_capacity(HISTORY_CAPACITY),
+            state_history: VecDeque::with_capacity(HISTORY_CAPACITY),
+            pattern: None,
+        })
+    }
+
+    fn with_behavior(
+        behavior: DecisionTable,
+        canvas_width: usize,
+        canvas_height: usize,
+        pixel_ratio: usize,
+        boundary: Boundary,
+        rng: &mut StdRng,
+    ) -> Turmite {
         let width = canvas_width / pixel_ratio;
         let height = canvas_height / pixel_ratio;
-        let field = vec![vec![false; height]; width];
+        let field = Rc::new(RefCell::new(Field::new(width, height)));
 
-        let mut rng = rand::thread_rng();
-        let state = rng.gen();
+        Turmite::new_on_field(
+            behavior,
+            GridSpec {
+                width,
+                height,
+                pixel_ratio,
+                boundary,
+                field,
+            },
+            ANT_COLOR,
+            rng,
+        )
+    }
 
-        let behavior = DecisionTable::random();
-        log!("Using {} as behavior table", behavior.name);
+    // Builds a Turmite that shares an existing grid instead of owning one,
+    // so a `Colony` can run several ants over the same field.
+    fn new_on_field(
+        behavior: DecisionTable,
+        grid: GridSpec,
+        ant_color: &'static str,
+        rng: &mut StdRng,
+    ) -> Turmite {
+        let GridSpec {
+            width,
+            height,
+            pixel_ratio,
+            boundary,
+            field,
+        } = grid;
+
+        let state = rng.gen::<usize>() % behavior.states;
+        let palette = default_palette(behavior.colors);
 
         let mut turmite = Turmite {
             x: width / 2,
             y: height / 2,
             orientation: Orientation::Right,
             is_active: true,
-            behavior,
             state,
+            palette,
             field,
             width,
             height,
             pixel_ratio,
+            behavior,
+            boundary,
+            ant_color,
+            steps: 0,
+            move_history: VecDeque::with_capacity(HISTORY_CAPACITY),
+            state_history: VecDeque::with_capacity(HISTORY_CAPACITY),
+            pattern: None,
         };
 
-        turmite.set_color(rng.gen());
+        let color = rng.gen::<usize>() % turmite.behavior.colors;
+        turmite.set_color(color as u8);
 
         turmite
     }
 
-    fn cur_color(&self) -> bool {
-        self.field[self.x][self.y]
+    fn cur_color(&self) -> u8 {
+        self.field.borrow().get(self.x, self.y)
     }
 
-    fn set_color(&mut self, v: bool) {
-        self.field[self.x][self.y] = v;
+    fn set_color(&mut self, v: u8) {
+        self.field.borrow_mut().set(self.x, self.y, v);
     }
 
     fn move_by(&mut self, dx: i32, dy: i32) {
         // log!("Moving by {} {}", dx, dy);
-        let mut new_x = self.x as i32 + dx;
-        if new_x < 0 {
-            self.is_active = false;
-            new_x = 0;
-        }
+        match self.boundary {
+            Boundary::Wrap => {
+                let width = self.width as i32;
+                let height = self.height as i32;
 
-        let mut new_y = self.y as i32 + dy;
-        if new_y < 0 {
-            self.is_active = false;
-            new_y = 0;
-        }
+                self.x = (((self.x as i32 + dx) % width + width) % width) as usize;
+                self.y = (((self.y as i32 + dy) % height + height) % height) as usize;
+            }
+            Boundary::Reflect => {
+                let width = self.width as i32;
+                let height = self.height as i32;
+
+                let mut new_x = self.x as i32 + dx;
+                if new_x < 0 || new_x >= width {
+                    self.orientation = self.orientation.uturn();
+                    new_x = self.x as i32;
+                }
+
+                let mut new_y = self.y as i32 + dy;
+                if new_y < 0 || new_y >= height {
+                    self.orientation = self.orientation.uturn();
+                    new_y = self.y as i32;
+                }
+
+                self.x = new_x as usize;
+                self.y = new_y as usize;
+            }
+            Boundary::Kill => {
+                let width = self.width as i32;
+                let height = self.height as i32;
+
+                let mut new_x = self.x as i32 + dx;
+                if new_x < 0 || new_x >= width {
+                    self.is_active = false;
+                    new_x = self.x as i32;
+                }
+
+                let mut new_y = self.y as i32 + dy;
+                if new_y < 0 || new_y >= height {
+                    self.is_active = false;
+                    new_y = self.y as i32;
+                }
 
-        self.x = new_x as usize;
-        self.y = new_y as usize;
+                self.x = new_x as usize;
+                self.y = new_y as usize;
+            }
+        }
     }
 
     fn tick_state(&mut self) {
-        let x = if self.state { 1 } else { 0 };
-        let y = if self.cur_color() { 1 } else { 0 };
+        let state = self.state;
+        let color = self.cur_color() as usize;
         let Decision {
             rotate,
             color,
             state,
-        } = self.behavior.decide(x, y);
+        } = self.behavior.decide(state, color);
 
         self.state = state;
         self.rotate(rotate);
-        self.set_color(color);
+        self.set_color(color as u8);
     }
 
     fn rotate(&mut self, rotation: Rotate) {
@@ -341,18 +848,125 @@ impl Turmite {
     fn tick_pos(&mut self) {
         use Orientation::*;
 
-        if self.x < self.width && self.y < self.height {
-            match &self.orientation {
-                Up => self.move_by(0, -1),
-                Right => self.move_by(1, 0),
-                Down => self.move_by(0, 1),
-                Left => self.move_by(-1, 0),
+        let on_grid = self.x < self.width && self.y < self.height;
+
+        if self.boundary != Boundary::Kill || on_grid {
+            let (dx, dy) = match &self.orientation {
+                Up => (0, -1),
+                Right => (1, 0),
+                Down => (0, 1),
+                Left => (-1, 0),
             };
+
+            self.move_by(dx, dy);
+            self.record_step(dx, dy);
         } else {
             self.is_active = false;
         }
     }
 
+    // Hashes position, orientation, state, and the nearby field cells.
+    fn state_signature(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.x.hash(&mut hasher);
+        self.y.hash(&mut hasher);
+        orientation_index(&self.orientation).hash(&mut hasher);
+        self.state.hash(&mut hasher);
+
+        let field = self.field.borrow();
+        for nx in (self.x as i32 - NEIGHBORHOOD_RADIUS)..=(self.x as i32 + NEIGHBORHOOD_RADIUS) {
+            for ny in
+                (self.y as i32 - NEIGHBORHOOD_RADIUS)..=(self.y as i32 + NEIGHBORHOOD_RADIUS)
+            {
+                let cell = if nx >= 0 && ny >= 0 && (nx as usize) < self.width && (ny as usize) < self.height
+                {
+                    field.get(nx as usize, ny as usize)
+                } else {
+                    0
+                };
+
+                cell.hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+
+    // Records one more step into the history ring buffers and refreshes the
+    // detected pattern, if any.
+    fn record_step(&mut self, dx: i32, dy: i32) {
+        self.steps += 1;
+
+        if self.move_history.len() == HISTORY_CAPACITY {
+            self.move_history.pop_front();
+        }
+        self.move_history.push_back((dx, dy));
+
+        let signature = self.state_signature();
+        if self.state_history.len() == HISTORY_CAPACITY {
+            self.state_history.pop_front();
+        }
+        self.state_history.push_back(signature);
+
+        self.pattern = self
+            .detect_static_cycle()
+            .map(|period| format!("static cycle (period {})", period))
+            .or_else(|| {
+                self.detect_highway()
+                    .map(|(period, (dx, dy))| format!("highway (period {}, drift ({}, {}))", period, dx, dy))
+            });
+    }
+
+    // A static cycle: the most recent state signature already appeared
+    // earlier in the history, meaning the ant, its state, and the local
+    // field are exactly as they were `period` steps ago.
+    fn detect_static_cycle(&self) -> Option<usize> {
+        let n = self.state_history.len();
+        if n < 2 {
+            return None;
+        }
+
+        let current = self.state_history[n - 1];
+
+        (0..n - 1)
+            .rev()
+            .find(|&i| self.state_history[i] == current)
+            .map(|i| n - 1 - i)
+    }
+
+    // The highway regime: the shortest period `p` such that the last
+    // `HIGHWAY_MIN_REPEATS * p` move vectors are made of `p`-long chunks
+    // that all repeat, with a nonzero net displacement per period.
+    fn detect_highway(&self) -> Option<(usize, (i32, i32))> {
+        let n = self.move_history.len();
+        let max_period = (n / HIGHWAY_MIN_REPEATS).min(HIGHWAY_MAX_PERIOD);
+
+        'period: for period in 1..=max_period {
+            let window = period * HIGHWAY_MIN_REPEATS;
+            let mut drift = (0, 0);
+
+            for offset in 0..period {
+                let first = self.move_history[n - window + offset];
+
+                for repeat in 1..HIGHWAY_MIN_REPEATS {
+                    let step = self.move_history[n - window + repeat * period + offset];
+                    if step != first {
+                        continue 'period;
+                    }
+                }
+
+                drift.0 += first.0;
+                drift.1 += first.1;
+            }
+
+            if drift != (0, 0) {
+                return Some((period, drift));
+            }
+        }
+
+        None
+    }
+
     fn render(&self, ctx: &CanvasRenderingContext2d, color: &str) {
         if self.is_active() {
             ctx.set_fill_style(&JsValue::from(color));
@@ -366,15 +980,16 @@ impl Turmite {
     }
 
     fn render_self(&self, ctx: &CanvasRenderingContext2d) {
-        self.render(ctx, ANT_COLOR);
+        self.render(ctx, self.ant_color);
     }
 
     fn render_cell(&self, ctx: &CanvasRenderingContext2d) {
-        let color = if self.cur_color() {
-            FILL_COLOR
-        } else {
-            EMPTY_COLOR
-        };
+        let color_index = self.cur_color() as usize;
+        let color = self
+            .palette
+            .get(color_index)
+            .map(String::as_str)
+            .unwrap_or(EMPTY_COLOR);
 
         self.render(ctx, color);
     }
@@ -387,6 +1002,24 @@ impl Turmite {
         self.is_active
     }
 
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn filled_cells(&self) -> usize {
+        self.field
+            .borrow()
+            .cells
+            .iter()
+            .flatten()
+            .filter(|&&c| c != 0)
+            .count()
+    }
+
+    pub fn detected_pattern(&self) -> Option<String> {
+        self.pattern.clone()
+    }
+
     pub fn tick(&mut self, ctx: &CanvasRenderingContext2d) {
         if self.is_active() {
             self.tick_state();
@@ -401,9 +1034,9 @@ impl fmt::Display for Turmite {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut field_output = "".to_string();
 
-        for row in &self.field {
+        for row in &self.field.borrow().cells {
             for cell in row {
-                field_output += if *cell { "1" } else { "0" };
+                field_output += &cell.to_string();
             }
         }
 
@@ -415,6 +1048,132 @@ impl fmt::Display for Turmite {
     }
 }
 
+// Drives many Turmite agents over one shared field.
+#[wasm_bindgen]
+pub struct Colony {
+    width: usize,
+    height: usize,
+    pixel_ratio: usize,
+    boundary: Boundary,
+    field: Rc<RefCell<Field>>,
+    ants: Vec<Turmite>,
+    rng: StdRng,
+    colors: Option<usize>,
+}
+
+#[wasm_bindgen]
+impl Colony {
+    // `seed` reproduces the same colony (rule choices, initial states and
+    // colors) across machines, the same way it does for a lone `Turmite`.
+    pub fn new(
+        canvas_width: usize,
+        canvas_height: usize,
+        pixel_ratio: usize,
+        count: usize,
+        boundary: Boundary,
+        seed: Option<u64>,
+    ) -> Colony {
+        let width = canvas_width / pixel_ratio;
+        let height = canvas_height / pixel_ratio;
+        let field = Rc::new(RefCell::new(Field::new(width, height)));
+
+        let mut colony = Colony {
+            width,
+            height,
+            pixel_ratio,
+            boundary,
+            field,
+            ants: Vec::with_capacity(count),
+            rng: make_rng(seed),
+            colors: None,
+        };
+
+        for _ in 0..count {
+            let behavior = DecisionTable::random(&mut colony.rng);
+            colony.spawn(behavior);
+        }
+
+        colony
+    }
+
+    // Adds one more ant driven by a rule shared as text (see
+    // `DecisionTable::from_rule`), reading/writing the colony's shared field.
+    // Ants sharing a field must agree on the number of colors, since a color
+    // written by one ant is read back by every other ant's `decide()`.
+    pub fn add_ant(&mut self, behavior_rule: &str, colors: usize) -> Result<(), JsValue> {
+        let behavior =
+            DecisionTable::from_rule(behavior_rule, colors).map_err(|e| JsValue::from_str(&e))?;
+
+        if let Some(established) = self.colors {
+            if established != behavior.colors {
+                return Err(JsValue::from_str(&format!(
+                    "Colony uses {} colors, but this rule uses {}",
+                    established, behavior.colors
+                )));
+            }
+        }
+
+        self.spawn(behavior);
+
+        Ok(())
+    }
+
+    pub fn tick(&mut self, ctx: &CanvasRenderingContext2d) {
+        for ant in self.ants.iter_mut() {
+            ant.tick(ctx);
+        }
+
+        self.resolve_collisions();
+    }
+
+    pub fn ant_count(&self) -> usize {
+        self.ants.len()
+    }
+}
+
+impl Colony {
+    // Ants that end a tick on the same cell collide and are both
+    // deactivated, rather than silently overlapping forever.
+    fn resolve_collisions(&mut self) {
+        let positions: Vec<(usize, usize)> = self.ants.iter().map(|ant| (ant.x, ant.y)).collect();
+        let active: Vec<bool> = self.ants.iter().map(|ant| ant.is_active).collect();
+
+        for i in 0..self.ants.len() {
+            if !active[i] {
+                continue;
+            }
+
+            let collided = (0..self.ants.len())
+                .any(|j| j != i && active[j] && positions[j] == positions[i]);
+
+            if collided {
+                self.ants[i].is_active = false;
+            }
+        }
+    }
+
+    fn spawn(&mut self, behavior: DecisionTable) {
+        self.colors.get_or_insert(behavior.colors);
+
+        let ant_color = ANT_COLORS[self.ants.len() % ANT_COLORS.len()];
+
+        let ant = Turmite::new_on_field(
+            behavior,
+            GridSpec {
+                width: self.width,
+                height: self.height,
+                pixel_ratio: self.pixel_ratio,
+                boundary: self.boundary,
+                field: Rc::clone(&self.field),
+            },
+            ant_color,
+            &mut self.rng,
+        );
+
+        self.ants.push(ant);
+    }
+}
+
 #[wasm_bindgen]
 pub fn debug() {
     utils::set_panic_hook();